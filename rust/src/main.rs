@@ -1,9 +1,103 @@
 use anyhow::{Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
+use rayon::prelude::*;
 use std::collections::HashMap;
 use std::env;
-use std::path::Path;
+use std::os::unix::fs::FileTypeExt;
+use std::path::{Path, PathBuf};
 use std::process;
-use walkdir::WalkDir;
+use std::sync::Mutex;
+
+/// Synthetic label used for directory entries when `--apparent-size` is set.
+const DIRECTORY_LABEL: &str = "directory";
+/// Synthetic label used for symlinks when `--apparent-size` is set.
+const SYMLINK_LABEL: &str = "symlink";
+/// Synthetic label used for block devices (unless `--regular-only` is set).
+const BLOCK_DEVICE_LABEL: &str = "block-device";
+/// Synthetic label used for character devices (unless `--regular-only` is set).
+const CHAR_DEVICE_LABEL: &str = "char-device";
+/// Synthetic label used for named pipes (unless `--regular-only` is set).
+const FIFO_LABEL: &str = "fifo";
+/// Synthetic label used for Unix domain sockets (unless `--regular-only` is set).
+const SOCKET_LABEL: &str = "socket";
+/// Number of files submitted to Magika per batch-identification call.
+const BATCH_SIZE: usize = 64;
+
+/// Command-line options for `scan_directory`.
+#[derive(Clone)]
+struct Options {
+    directory: String,
+    apparent_size: bool,
+    jobs: usize,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    regular_only: bool,
+    by_dir: bool,
+    max_depth: Option<usize>,
+    no_ignore: bool,
+    hidden: bool,
+    output: OutputFormat,
+    verbose: bool,
+}
+
+/// Output format selected via `--output`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+impl OutputFormat {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "table" => Some(OutputFormat::Table),
+            "json" => Some(OutputFormat::Json),
+            "csv" => Some(OutputFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// Per-label byte and file-count totals, keyed by Magika label (or a
+/// synthetic label such as [`DIRECTORY_LABEL`]/[`SYMLINK_LABEL`]).
+#[derive(Default)]
+struct TypeTotals {
+    bytes: HashMap<String, u64>,
+    counts: HashMap<String, u64>,
+}
+
+impl TypeTotals {
+    /// Records one entry of `size` bytes under `label`.
+    fn add(&mut self, label: impl Into<String>, size: u64) {
+        let label = label.into();
+        *self.bytes.entry(label.clone()).or_insert(0) += size;
+        *self.counts.entry(label).or_insert(0) += 1;
+    }
+
+    /// Merges another set of totals into this one.
+    fn merge(&mut self, other: TypeTotals) {
+        for (label, bytes) in other.bytes {
+            *self.bytes.entry(label).or_insert(0) += bytes;
+        }
+        for (label, count) in other.counts {
+            *self.counts.entry(label).or_insert(0) += count;
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn total_bytes(&self) -> u64 {
+        self.bytes.values().sum()
+    }
+
+    fn total_files(&self) -> u64 {
+        self.counts.values().sum()
+    }
+}
 
 fn main() {
     if let Err(e) = run() {
@@ -15,46 +109,373 @@ fn main() {
 fn run() -> Result<()> {
     // Parse command-line arguments
     let args: Vec<String> = env::args().collect();
-    let directory = args.get(1).map(|s| s.as_str()).unwrap_or(".");
+    let options = parse_args(&args[1..]);
 
     // Validate directory path
-    let path = Path::new(directory);
+    let path = Path::new(&options.directory);
     if !path.exists() {
-        eprintln!("Error: Directory '{}' does not exist.", directory);
+        eprintln!("Error: Directory '{}' does not exist.", options.directory);
         process::exit(1);
     }
     if !path.is_dir() {
-        eprintln!("Error: '{}' is not a directory.", directory);
+        eprintln!("Error: '{}' is not a directory.", options.directory);
         process::exit(1);
     }
 
     // Scan directory and collect file type sizes
-    let file_type_sizes = scan_directory(path)?;
+    let (totals, records) = scan_directory(path, &options)?;
+
+    // When ignore rules are in effect, report how many bytes they filtered
+    // out so users understand what was excluded from the report. Only
+    // bother computing it when something will actually display it: the
+    // table and by-dir views show the note, json/csv never read it.
+    let skipped_by_ignore = if options.no_ignore {
+        None
+    } else if options.by_dir || options.output == OutputFormat::Table {
+        Some(compute_ignored_bytes(path, &options, totals.total_bytes()))
+    } else {
+        None
+    };
+
+    if options.by_dir {
+        let records = filter_records_by_label(records, &options)?;
+        let by_dir = build_dir_totals(&records, path, options.max_depth);
+        display_by_dir(&by_dir, path, skipped_by_ignore);
+        return Ok(());
+    }
+
+    // Apply --include/--exclude label filters, if any
+    let (totals, excluded) = apply_label_filter(totals, &options)?;
 
     // Display results
-    display_results(&file_type_sizes);
+    display_results(
+        &totals,
+        excluded.as_ref(),
+        skipped_by_ignore,
+        options.output,
+    );
 
     Ok(())
 }
 
-/// Recursively scans a directory and aggregates file sizes by type
-fn scan_directory(directory: &Path) -> Result<HashMap<String, u64>> {
-    let mut magika = magika::Session::new().context("Failed to initialize Magika")?;
-    let mut file_type_sizes: HashMap<String, u64> = HashMap::new();
+/// Re-walks `directory` with `.gitignore`/`.ignore` filtering disabled (but
+/// every other option — `--hidden`, `--apparent-size`, `--regular-only` —
+/// held identical to the real scan) and returns how many more bytes that
+/// walk sees than `scanned_total`. Reusing `walk_directory` with only
+/// `no_ignore` flipped keeps the two counts apples-to-apples: hidden files
+/// excluded by `--hidden`'s default, for instance, are excluded from both
+/// walks alike and never show up as "skipped by ignore rules".
+fn compute_ignored_bytes(directory: &Path, options: &Options, scanned_total: u64) -> u64 {
+    let mut unfiltered = options.clone();
+    unfiltered.no_ignore = true;
+    unfiltered.by_dir = false;
 
-    for entry in WalkDir::new(directory)
-        .follow_links(false)
+    let (unfiltered_totals, unfiltered_files, _) = walk_directory(directory, &unfiltered);
+    let unfiltered_total = unfiltered_totals.total_bytes()
+        + unfiltered_files.iter().map(|(_, size)| size).sum::<u64>();
+
+    unfiltered_total.saturating_sub(scanned_total)
+}
+
+/// Parses command-line arguments into [`Options`], treating the first
+/// non-flag argument as the directory to scan.
+fn parse_args(args: &[String]) -> Options {
+    let mut directory = None;
+    let mut apparent_size = false;
+    let mut jobs = default_jobs();
+    let mut include = Vec::new();
+    let mut exclude = Vec::new();
+    let mut regular_only = false;
+    let mut by_dir = false;
+    let mut max_depth = None;
+    let mut no_ignore = false;
+    let mut hidden = false;
+    let mut output = OutputFormat::Table;
+    let mut verbose = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--apparent-size" | "--du" => apparent_size = true,
+            "--regular-only" => regular_only = true,
+            "--by-dir" => by_dir = true,
+            "--no-ignore" => no_ignore = true,
+            "--hidden" => hidden = true,
+            "--verbose" => verbose = true,
+            "--output" => {
+                if let Some(value) = iter.next() {
+                    if let Some(format) = OutputFormat::parse(value) {
+                        output = format;
+                    }
+                }
+            }
+            "--jobs" => {
+                if let Some(value) = iter.next() {
+                    if let Ok(n) = value.parse::<usize>() {
+                        jobs = n.max(1);
+                    }
+                }
+            }
+            "--max-depth" => {
+                if let Some(value) = iter.next() {
+                    if let Ok(n) = value.parse::<usize>() {
+                        max_depth = Some(n);
+                    }
+                }
+            }
+            "--include" => {
+                if let Some(value) = iter.next() {
+                    include.push(value.clone());
+                }
+            }
+            "--exclude" => {
+                if let Some(value) = iter.next() {
+                    exclude.push(value.clone());
+                }
+            }
+            _ => {
+                if directory.is_none() {
+                    directory = Some(arg.clone());
+                }
+            }
+        }
+    }
+
+    Options {
+        directory: directory.unwrap_or_else(|| ".".to_string()),
+        apparent_size,
+        jobs,
+        include,
+        exclude,
+        regular_only,
+        by_dir,
+        max_depth,
+        no_ignore,
+        hidden,
+        output,
+        verbose,
+    }
+}
+
+/// Default parallelism for `--jobs`: the number of available CPU cores.
+fn default_jobs() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+/// Builds a [`GlobSet`] from glob patterns such as `image/*`, or `None` if
+/// `patterns` is empty.
+fn build_globset(patterns: &[String]) -> Result<Option<GlobSet>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(
+            Glob::new(pattern).with_context(|| format!("Invalid glob pattern '{}'", pattern))?,
+        );
+    }
+    Ok(Some(
+        builder.build().context("Failed to build glob matcher")?,
+    ))
+}
+
+/// Restricts `totals` to labels matching `--include`/`--exclude`, returning
+/// the filtered totals and, if a filter was active, the totals excluded by
+/// it (so the caller can report what was dropped).
+fn apply_label_filter(
+    totals: TypeTotals,
+    options: &Options,
+) -> Result<(TypeTotals, Option<TypeTotals>)> {
+    if options.include.is_empty() && options.exclude.is_empty() {
+        return Ok((totals, None));
+    }
+
+    let include_set = build_globset(&options.include)?;
+    let exclude_set = build_globset(&options.exclude)?;
+
+    let mut kept = TypeTotals::default();
+    let mut excluded = TypeTotals::default();
+
+    for (label, bytes) in totals.bytes {
+        let count = *totals.counts.get(&label).unwrap_or(&0);
+        let included = include_set
+            .as_ref()
+            .map(|set| set.is_match(&label))
+            .unwrap_or(true);
+        let excluded_match = exclude_set
+            .as_ref()
+            .map(|set| set.is_match(&label))
+            .unwrap_or(false);
+
+        let target = if included && !excluded_match {
+            &mut kept
+        } else {
+            &mut excluded
+        };
+        *target.bytes.entry(label.clone()).or_insert(0) += bytes;
+        *target.counts.entry(label).or_insert(0) += count;
+    }
+
+    Ok((kept, Some(excluded)))
+}
+
+/// Restricts `records` to entries whose label matches `--include`/`--exclude`,
+/// the same precedence `apply_label_filter` uses (`included && !excluded_match`).
+/// Used by the `--by-dir` path, which aggregates per directory instead of
+/// through [`TypeTotals`] and so can't reuse `apply_label_filter` directly.
+fn filter_records_by_label(
+    records: Vec<(PathBuf, String, u64)>,
+    options: &Options,
+) -> Result<Vec<(PathBuf, String, u64)>> {
+    if options.include.is_empty() && options.exclude.is_empty() {
+        return Ok(records);
+    }
+
+    let include_set = build_globset(&options.include)?;
+    let exclude_set = build_globset(&options.exclude)?;
+
+    Ok(records
         .into_iter()
-        .filter_map(|e| e.ok())
-    {
+        .filter(|(_, label, _)| {
+            let included = include_set
+                .as_ref()
+                .map(|set| set.is_match(label))
+                .unwrap_or(true);
+            let excluded_match = exclude_set
+                .as_ref()
+                .map(|set| set.is_match(label))
+                .unwrap_or(false);
+            included && !excluded_match
+        })
+        .collect())
+}
+
+/// Recursively scans a directory and aggregates file sizes by type.
+///
+/// The scan runs in two phases: [`walk_directory`] collects regular files
+/// (while directly accounting for symlinks and directory entries), then
+/// the files are handed to Magika in batches, optionally spreading the
+/// batches across a thread pool sized by `options.jobs`.
+fn scan_directory(
+    directory: &Path,
+    options: &Options,
+) -> Result<(TypeTotals, Vec<(PathBuf, String, u64)>)> {
+    let (mut totals, files, mut records) = walk_directory(directory, options);
+
+    if options.jobs <= 1 {
+        identify_files_sequential(&files, &mut totals, &mut records, options.by_dir)?;
+    } else {
+        identify_files_parallel(
+            &files,
+            &mut totals,
+            &mut records,
+            options.jobs,
+            options.by_dir,
+        )?;
+    }
+
+    Ok((totals, records))
+}
+
+/// Walks `directory`, directly accounting for symlinks and directory entries
+/// (subject to `options.apparent_size`), and returns the regular files still
+/// needing Magika identification alongside their sizes. When `options.by_dir`
+/// is set, every synthetic entry is also appended to the returned records so
+/// [`build_dir_totals`] can break them down per directory.
+///
+/// Traversal goes through the `ignore` crate so `.gitignore`, `.ignore`, and
+/// global git excludes are honored by default; `options.no_ignore` disables
+/// all of that filtering and `options.hidden` additionally includes dotfiles.
+fn walk_directory(
+    directory: &Path,
+    options: &Options,
+) -> (TypeTotals, Vec<(PathBuf, u64)>, Vec<(PathBuf, String, u64)>) {
+    let mut totals = TypeTotals::default();
+    let mut files: Vec<(PathBuf, u64)> = Vec::new();
+    let mut records: Vec<(PathBuf, String, u64)> = Vec::new();
+
+    let walker = WalkBuilder::new(directory)
+        .standard_filters(!options.no_ignore)
+        .hidden(!options.hidden)
+        .follow_links(false)
+        .build();
+
+    for entry in walker.filter_map(|e| e.ok()) {
         let path = entry.path();
 
-        // Skip directories and symlinks
-        if !path.is_file() || path.is_symlink() {
+        if path.is_symlink() {
+            if !options.apparent_size {
+                continue;
+            }
+
+            // `symlink_metadata()` does not follow the link, so it sizes the
+            // link itself and also succeeds for broken/dangling symlinks,
+            // which are accounted for instead of aborting the scan.
+            let size = match path.symlink_metadata() {
+                Ok(metadata) => metadata.len(),
+                Err(e) => {
+                    eprintln!(
+                        "Warning: Could not access symlink {}: {}",
+                        path.display(),
+                        e
+                    );
+                    continue;
+                }
+            };
+            // Only pay for the extra `metadata()` stat (to check whether the
+            // link is dangling) when the caller actually asked to see it.
+            if options.verbose {
+                if let Err(e) = path.metadata() {
+                    eprintln!("Debug: {} is a broken symlink: {}", path.display(), e);
+                }
+            }
+
+            totals.add(SYMLINK_LABEL, size);
+            if options.by_dir {
+                records.push((path.to_path_buf(), SYMLINK_LABEL.to_string(), size));
+            }
+            continue;
+        }
+
+        if path.is_dir() {
+            if options.apparent_size {
+                let size = match path.metadata() {
+                    Ok(metadata) => metadata.len(),
+                    Err(e) => {
+                        eprintln!("Warning: Could not access {}: {}", path.display(), e);
+                        continue;
+                    }
+                };
+                totals.add(DIRECTORY_LABEL, size);
+                if options.by_dir {
+                    records.push((path.to_path_buf(), DIRECTORY_LABEL.to_string(), size));
+                }
+            }
+            continue;
+        }
+
+        if !path.is_file() {
+            if !options.regular_only {
+                if let Some(label) = special_file_label(&entry) {
+                    let size = match path.metadata() {
+                        Ok(metadata) => metadata.len(),
+                        Err(e) => {
+                            eprintln!("Warning: Could not access {}: {}", path.display(), e);
+                            continue;
+                        }
+                    };
+                    totals.add(label, size);
+                    if options.by_dir {
+                        records.push((path.to_path_buf(), label.to_string(), size));
+                    }
+                }
+            }
             continue;
         }
 
-        // Get file size
         let size = match path.metadata() {
             Ok(metadata) => metadata.len(),
             Err(e) => {
@@ -63,7 +484,42 @@ fn scan_directory(directory: &Path) -> Result<HashMap<String, u64>> {
             }
         };
 
-        // Identify file type using Magika
+        files.push((path.to_path_buf(), size));
+    }
+
+    (totals, files, records)
+}
+
+/// Classifies a non-regular, non-directory, non-symlink `ignore` walker
+/// entry into one of the synthetic special-file labels, or `None` if it
+/// doesn't match any of them (Magika only ever runs on regular files).
+fn special_file_label(entry: &ignore::DirEntry) -> Option<&'static str> {
+    let file_type = entry.file_type()?;
+    if file_type.is_block_device() {
+        Some(BLOCK_DEVICE_LABEL)
+    } else if file_type.is_char_device() {
+        Some(CHAR_DEVICE_LABEL)
+    } else if file_type.is_fifo() {
+        Some(FIFO_LABEL)
+    } else if file_type.is_socket() {
+        Some(SOCKET_LABEL)
+    } else {
+        None
+    }
+}
+
+/// Identifies `files` one at a time on a single Magika `Session`. This is
+/// the fallback used for `--jobs 1` and mirrors the tool's original,
+/// pre-batching behavior.
+fn identify_files_sequential(
+    files: &[(PathBuf, u64)],
+    totals: &mut TypeTotals,
+    records: &mut Vec<(PathBuf, String, u64)>,
+    by_dir: bool,
+) -> Result<()> {
+    let mut magika = magika::Session::new().context("Failed to initialize Magika")?;
+
+    for (path, size) in files {
         let file_type = match magika.identify_file_sync(path) {
             Ok(result) => result.info().label.to_string(),
             Err(e) => {
@@ -76,11 +532,178 @@ fn scan_directory(directory: &Path) -> Result<HashMap<String, u64>> {
             }
         };
 
-        // Aggregate size by file type
-        *file_type_sizes.entry(file_type).or_insert(0) += size;
+        totals.add(file_type.clone(), *size);
+        if by_dir {
+            records.push((path.clone(), file_type, *size));
+        }
+    }
+
+    Ok(())
+}
+
+/// Identifies `files` in fixed-size batches spread across `jobs` threads.
+/// Magika `Session`s are created through `for_each_init`, which loads one
+/// per rayon job (a group of batches) and reuses it for every batch in that
+/// job. Rayon does not bound how many jobs it splits the work into, so the
+/// ONNX model may be loaded more than `jobs` times, but never more than once
+/// per `BATCH_SIZE`-file chunk.
+fn identify_files_parallel(
+    files: &[(PathBuf, u64)],
+    totals: &mut TypeTotals,
+    records: &mut Vec<(PathBuf, String, u64)>,
+    jobs: usize,
+    by_dir: bool,
+) -> Result<()> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .context("Failed to build Magika worker thread pool")?;
+
+    let aggregated: Mutex<TypeTotals> = Mutex::new(TypeTotals::default());
+    let aggregated_records: Mutex<Vec<(PathBuf, String, u64)>> = Mutex::new(Vec::new());
+
+    pool.install(|| {
+        files.par_chunks(BATCH_SIZE).for_each_init(
+            || magika::Session::new(),
+            |session, batch| {
+                let magika = match session {
+                    Ok(session) => session,
+                    Err(e) => {
+                        eprintln!(
+                            "Warning: Failed to initialize Magika on worker thread: {}",
+                            e
+                        );
+                        return;
+                    }
+                };
+
+                let paths: Vec<&Path> = batch.iter().map(|(path, _)| path.as_path()).collect();
+                let results = match magika.identify_files_sync(&paths) {
+                    Ok(results) => results,
+                    Err(e) => {
+                        eprintln!("Warning: Batch identification failed: {}", e);
+                        return;
+                    }
+                };
+
+                let mut local = TypeTotals::default();
+                let mut local_records: Vec<(PathBuf, String, u64)> = Vec::new();
+                for ((path, size), result) in batch.iter().zip(results) {
+                    match result {
+                        Ok(info) => {
+                            let label = info.info().label.to_string();
+                            local.add(label.clone(), *size);
+                            if by_dir {
+                                local_records.push((path.clone(), label, *size));
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!(
+                                "Warning: Could not identify file type for {}: {}",
+                                path.display(),
+                                e
+                            );
+                        }
+                    }
+                }
+
+                aggregated.lock().unwrap().merge(local);
+                if by_dir {
+                    aggregated_records.lock().unwrap().extend(local_records);
+                }
+            },
+        );
+    });
+
+    totals.merge(aggregated.into_inner().unwrap());
+    records.extend(aggregated_records.into_inner().unwrap());
+
+    Ok(())
+}
+
+/// Groups `records` by the ancestor directory of each entry's path, relative
+/// to `root` and truncated to `max_depth` path components (unlimited depth
+/// when `max_depth` is `None`).
+fn build_dir_totals(
+    records: &[(PathBuf, String, u64)],
+    root: &Path,
+    max_depth: Option<usize>,
+) -> HashMap<PathBuf, TypeTotals> {
+    let mut by_dir: HashMap<PathBuf, TypeTotals> = HashMap::new();
+
+    for (path, label, size) in records {
+        let parent = path.parent().unwrap_or(root);
+        let relative = parent.strip_prefix(root).unwrap_or(parent);
+        let truncated: PathBuf = match max_depth {
+            Some(depth) => relative.components().take(depth).collect(),
+            None => relative.to_path_buf(),
+        };
+        let key = root.join(truncated);
+
+        by_dir.entry(key).or_default().add(label.clone(), *size);
+    }
+
+    by_dir
+}
+
+/// Renders the `--by-dir` breakdown as a nested, indented table: one block
+/// per directory (sorted by path, so parents print before their children),
+/// with that directory's types sorted by size descending underneath.
+fn display_by_dir(
+    by_dir: &HashMap<PathBuf, TypeTotals>,
+    root: &Path,
+    skipped_by_ignore: Option<u64>,
+) {
+    if by_dir.is_empty() {
+        println!("No files found.");
+        return;
+    }
+
+    let mut dirs: Vec<&PathBuf> = by_dir.keys().collect();
+    dirs.sort();
+
+    let separator = "=".repeat(70);
+    println!();
+
+    for dir in dirs {
+        let totals = &by_dir[dir];
+        let relative = dir.strip_prefix(root).unwrap_or(dir);
+        let depth = relative.components().count();
+        let indent = "  ".repeat(depth);
+        let label = if relative.as_os_str().is_empty() {
+            ".".to_string()
+        } else {
+            relative.display().to_string()
+        };
+
+        println!("{}{}", indent, separator);
+        println!(
+            "{}{} ({}, {} file(s))",
+            indent,
+            label,
+            format_size(totals.total_bytes()),
+            totals.total_files()
+        );
+
+        let mut sorted_types: Vec<(&String, &u64)> = totals.bytes.iter().collect();
+        sorted_types.sort_by(|a, b| b.1.cmp(a.1));
+        for (file_type, size) in sorted_types {
+            println!("{}  {:<28} {:<20}", indent, file_type, format_size(*size));
+        }
+    }
+
+    println!("{}", separator);
+
+    if let Some(skipped) = skipped_by_ignore {
+        if skipped > 0 {
+            println!(
+                "Note: {} skipped by .gitignore/.ignore rules (use --no-ignore to include)",
+                format_size(skipped)
+            );
+        }
     }
 
-    Ok(file_type_sizes)
+    println!();
 }
 
 /// Formats bytes into human-readable size (B, KB, MB, GB, TB, PB)
@@ -97,19 +720,60 @@ fn format_size(size_bytes: u64) -> String {
     format!("{:.2} {}", size, units[unit_index])
 }
 
-/// Displays results in a formatted table
-fn display_results(file_type_sizes: &HashMap<String, u64>) {
-    if file_type_sizes.is_empty() {
+/// Sorts `totals` by size descending and returns `(label, bytes, percentage)`
+/// rows, with percentage computed against `totals`' own total.
+fn sorted_rows(totals: &TypeTotals) -> Vec<(&str, u64, f64)> {
+    let total_size = totals.total_bytes();
+    let mut rows: Vec<(&str, u64, f64)> = totals
+        .bytes
+        .iter()
+        .map(|(label, size)| {
+            // A tree of only empty files (or total_size == 0 in general)
+            // would otherwise divide 0.0 / 0.0 into a literal NaN, which
+            // breaks the JSON/CSV output this percentage feeds into.
+            let percentage = if total_size == 0 {
+                0.0
+            } else {
+                (*size as f64 / total_size as f64) * 100.0
+            };
+            (label.as_str(), *size, percentage)
+        })
+        .collect();
+    rows.sort_by(|a, b| b.1.cmp(&a.1));
+    rows
+}
+
+/// Displays results in the format selected by `--output` (table, JSON, or
+/// CSV). The table format additionally reports `excluded`/`skipped_by_ignore`
+/// notes; JSON and CSV emit only the per-type aggregation for scripting.
+fn display_results(
+    totals: &TypeTotals,
+    excluded: Option<&TypeTotals>,
+    skipped_by_ignore: Option<u64>,
+    output: OutputFormat,
+) {
+    match output {
+        OutputFormat::Table => display_table(totals, excluded, skipped_by_ignore),
+        OutputFormat::Json => display_json(totals),
+        OutputFormat::Csv => display_csv(totals),
+    }
+}
+
+/// Displays results in a formatted table. When `excluded` is `Some` (i.e. an
+/// `--include`/`--exclude` filter was active), percentages are computed
+/// against the filtered total and a trailing note reports what was dropped.
+fn display_table(
+    totals: &TypeTotals,
+    excluded: Option<&TypeTotals>,
+    skipped_by_ignore: Option<u64>,
+) {
+    if totals.is_empty() {
         println!("No files found.");
         return;
     }
 
-    // Calculate total size
-    let total_size: u64 = file_type_sizes.values().sum();
-
-    // Sort file types by size (descending)
-    let mut sorted_types: Vec<(&String, &u64)> = file_type_sizes.iter().collect();
-    sorted_types.sort_by(|a, b| b.1.cmp(a.1));
+    let total_size = totals.total_bytes();
+    let rows = sorted_rows(totals);
 
     // Print table
     let separator = "=".repeat(70);
@@ -119,12 +783,11 @@ fn display_results(file_type_sizes: &HashMap<String, u64>) {
     println!("{:<30} {:<20} {:>10}", "File Type", "Size", "Percentage");
     println!("{}", separator);
 
-    for (file_type, size) in sorted_types {
-        let percentage = (*size as f64 / total_size as f64) * 100.0;
+    for (file_type, size, percentage) in rows {
         println!(
             "{:<30} {:<20} {:>9.2}%",
             file_type,
-            format_size(*size),
+            format_size(size),
             percentage
         );
     }
@@ -137,5 +800,215 @@ fn display_results(file_type_sizes: &HashMap<String, u64>) {
         100.0
     );
     println!("{}", separator);
+
+    if let Some(excluded) = excluded {
+        if !excluded.is_empty() {
+            println!(
+                "Note: {} file(s) ({}) excluded by --include/--exclude filters",
+                excluded.total_files(),
+                format_size(excluded.total_bytes())
+            );
+        }
+    }
+
+    if let Some(skipped) = skipped_by_ignore {
+        if skipped > 0 {
+            println!(
+                "Note: {} skipped by .gitignore/.ignore rules (use --no-ignore to include)",
+                format_size(skipped)
+            );
+        }
+    }
+
     println!();
 }
+
+/// Displays results as a JSON object: a `types` array of
+/// `{ "label", "bytes", "percentage" }` entries sorted by size descending,
+/// plus a top-level `total_bytes`.
+fn display_json(totals: &TypeTotals) {
+    let types: Vec<serde_json::Value> = sorted_rows(totals)
+        .into_iter()
+        .map(|(label, bytes, percentage)| {
+            serde_json::json!({
+                "label": label,
+                "bytes": bytes,
+                "percentage": percentage,
+            })
+        })
+        .collect();
+
+    let output = serde_json::json!({
+        "types": types,
+        "total_bytes": totals.total_bytes(),
+    });
+
+    match serde_json::to_string_pretty(&output) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("Error: Could not serialize results as JSON: {}", e),
+    }
+}
+
+/// Displays results as CSV (`label,bytes,percentage`), sorted by size
+/// descending, with a trailing `Total` row.
+fn display_csv(totals: &TypeTotals) {
+    let mut writer = csv::Writer::from_writer(std::io::stdout());
+
+    if let Err(e) = writer.write_record(["label", "bytes", "percentage"]) {
+        eprintln!("Error: Could not write CSV header: {}", e);
+        return;
+    }
+
+    for (label, bytes, percentage) in sorted_rows(totals) {
+        if let Err(e) = writer.write_record(&[
+            label.to_string(),
+            bytes.to_string(),
+            format!("{:.2}", percentage),
+        ]) {
+            eprintln!("Error: Could not write CSV row: {}", e);
+            return;
+        }
+    }
+
+    let _ = writer.write_record(&[
+        "Total".to_string(),
+        totals.total_bytes().to_string(),
+        "100.00".to_string(),
+    ]);
+
+    if let Err(e) = writer.flush() {
+        eprintln!("Error: Could not flush CSV output: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_options() -> Options {
+        Options {
+            directory: ".".to_string(),
+            apparent_size: false,
+            jobs: 1,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            regular_only: false,
+            by_dir: false,
+            max_depth: None,
+            no_ignore: false,
+            hidden: false,
+            output: OutputFormat::Table,
+            verbose: false,
+        }
+    }
+
+    #[test]
+    fn build_dir_totals_groups_by_immediate_parent_without_max_depth() {
+        let root = Path::new("/project");
+        let records = vec![
+            (
+                PathBuf::from("/project/src/main.rs"),
+                "rust".to_string(),
+                10,
+            ),
+            (PathBuf::from("/project/src/lib.rs"), "rust".to_string(), 20),
+            (
+                PathBuf::from("/project/docs/readme.md"),
+                "markdown".to_string(),
+                5,
+            ),
+        ];
+
+        let by_dir = build_dir_totals(&records, root, None);
+
+        assert_eq!(by_dir[Path::new("/project/src")].total_bytes(), 30);
+        assert_eq!(by_dir[Path::new("/project/docs")].total_bytes(), 5);
+    }
+
+    #[test]
+    fn build_dir_totals_truncates_to_max_depth() {
+        let root = Path::new("/project");
+        let records = vec![(
+            PathBuf::from("/project/a/b/c/deep.rs"),
+            "rust".to_string(),
+            42,
+        )];
+
+        // depth 0: everything collapses onto the root itself.
+        let by_dir = build_dir_totals(&records, root, Some(0));
+        assert_eq!(by_dir.len(), 1);
+        assert_eq!(by_dir[root].total_bytes(), 42);
+
+        // depth 2: truncated to the first two path components (a/b).
+        let by_dir = build_dir_totals(&records, root, Some(2));
+        assert_eq!(by_dir[&root.join("a/b")].total_bytes(), 42);
+
+        // unlimited depth: grouped under the full parent directory.
+        let by_dir = build_dir_totals(&records, root, None);
+        assert_eq!(by_dir[&root.join("a/b/c")].total_bytes(), 42);
+    }
+
+    #[test]
+    fn apply_label_filter_include_and_exclude_precedence() {
+        let mut totals = TypeTotals::default();
+        totals.add("text", 100);
+        totals.add("image/png", 50);
+        totals.add("image/jpeg", 25);
+
+        let mut options = test_options();
+        options.include = vec!["image/*".to_string()];
+        options.exclude = vec!["image/png".to_string()];
+
+        let (kept, excluded) = apply_label_filter(totals, &options).unwrap();
+        let excluded = excluded.unwrap();
+
+        // Matches include and isn't excluded: kept.
+        assert_eq!(kept.bytes.get("image/jpeg"), Some(&25));
+        // Matches include but exclude wins: dropped from kept.
+        assert!(kept.bytes.get("image/png").is_none());
+        assert_eq!(excluded.bytes.get("image/png"), Some(&50));
+        // Doesn't match include at all: dropped from kept.
+        assert_eq!(excluded.bytes.get("text"), Some(&100));
+    }
+
+    #[test]
+    fn apply_label_filter_no_filters_returns_totals_unchanged() {
+        let mut totals = TypeTotals::default();
+        totals.add("text", 100);
+
+        let options = test_options();
+        let (kept, excluded) = apply_label_filter(totals, &options).unwrap();
+
+        assert_eq!(kept.bytes.get("text"), Some(&100));
+        assert!(excluded.is_none());
+    }
+
+    #[test]
+    fn filter_records_by_label_matches_apply_label_filter_precedence() {
+        let records = vec![
+            (PathBuf::from("/a/one.png"), "image/png".to_string(), 50),
+            (PathBuf::from("/a/two.jpg"), "image/jpeg".to_string(), 25),
+            (PathBuf::from("/a/notes.txt"), "text".to_string(), 100),
+        ];
+
+        let mut options = test_options();
+        options.include = vec!["image/*".to_string()];
+        options.exclude = vec!["image/png".to_string()];
+
+        let filtered = filter_records_by_label(records, &options).unwrap();
+
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].1, "image/jpeg");
+    }
+
+    #[test]
+    fn sorted_rows_zero_total_size_yields_zero_percentage_not_nan() {
+        let mut totals = TypeTotals::default();
+        totals.add("empty", 0);
+        assert_eq!(totals.total_bytes(), 0);
+
+        let rows = sorted_rows(&totals);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].2, 0.0);
+    }
+}